@@ -30,9 +30,12 @@ const EXYNOS_TRNG_CTRL_RNGEN: usize = 0x1 << 31; // BIT(31)
 
 const EXYNOS_REG_SIZE: usize = 0x100;
 const EXYNOS_TRNG_POST_CTRL: usize = 0x30;
-const _EXYNOS_TRNG_ONLINE_CTRL: usize = 0x40;
-const _EXYNOS_TRNG_ONLINE_STAT: usize = 0x44;
-const _EXYNOS_TRNG_ONLINE_MAXCHI2: usize = 0x48;
+const EXYNOS_TRNG_POST_CTRL_EN: usize = 0x1;
+const EXYNOS_TRNG_ONLINE_CTRL: usize = 0x40;
+const EXYNOS_TRNG_ONLINE_CTRL_EN: usize = 0x1;
+const EXYNOS_TRNG_ONLINE_STAT: usize = 0x44;
+const EXYNOS_TRNG_ONLINE_STAT_FAIL: u32 = 0x1;
+const EXYNOS_TRNG_ONLINE_MAXCHI2: usize = 0x48;
 const EXYNOS_TRNG_FIFO_CTRL: usize = 0x50;
 const EXYNOS_TRNG_FIFO_0: usize = 0x80;
 const _EXYNOS_TRNG_FIFO_1: usize = 0x84;
@@ -45,6 +48,19 @@ const _EXYNOS_TRNG_FIFO_7: usize = 0x9c;
 const EXYNOS_TRNG_FIFO_LEN: u32 = 8;
 const EXYNOS_TRNG_CLOCK_RATE: usize = 500000;
 
+// Per-byte min-entropy estimate for the raw (post-processing disabled) output, expressed in
+// milli-bits, used to size the continuous Repetition Count Test. 8000 assumes full entropy.
+const EXYNOS_TRNG_MIN_ENTROPY_MILLIBITS: u32 = 8000;
+// SP 800-90B Table 3 Adaptive Proportion Test cutoff for H = 8 bits, alpha = 2^-20, W = 1024.
+const EXYNOS_TRNG_APT_CUTOFF: u32 = 18;
+// Keep the SSS power domain up for this long after the last read before autosuspending.
+const EXYNOS_TRNG_AUTOSUSPEND_DELAY_MS: i32 = 50;
+
+// A single continuous-test failure can be a one-off glitch, but this many since the device was
+// probed means the noise source itself is suspect; stop handing out bytes for good rather than
+// keep bouncing the same faulty TRNG in and out of an error state.
+const EXYNOS_TRNG_HEALTH_FAILURE_LIMIT: u32 = 8;
+
 struct ExynosTrngDevice;
 
 struct ExynosTrngDataInner {
@@ -55,6 +71,13 @@ struct ExynosTrngData {
     dev: device::Device,
     disable: pm_runtime::DisableGuard,
     inner: SpinLock<ExynosTrngDataInner>,
+    health: Box<hwrng::health::HealthTests>,
+    /// Whether the hardware post-processing block should be left enabled, rather than bypassed
+    /// so `/dev/hwrng` gets raw output. Taken from the `samsung,post-processing` DT property.
+    post_processing: bool,
+    /// Whether the TRNG's built-in online chi-square health monitor should be armed. Taken from
+    /// the `samsung,online-test` DT property.
+    online_monitor: bool,
 }
 
 struct ExynosTrngResources {
@@ -71,6 +94,8 @@ impl hwrng::Operations for ExynosTrngDevice {
 
     fn init(trng: RefBorrow<'_, DeviceData>) -> Result {
         let sss_rate = trng.inner.lock().clk.get_rate();
+        let post_processing = trng.post_processing;
+        let online_monitor = trng.online_monitor;
 
         // For most TRNG circuits the clock frequency of under 500 kHz
         // is safe.
@@ -89,25 +114,79 @@ impl hwrng::Operations for ExynosTrngDevice {
         val = EXYNOS_TRNG_CTRL_RNGEN;
         trng.mem.writel_relaxed(val.try_into()?, EXYNOS_TRNG_CTRL);
 
-        // Disable post-processing. /dev/hwrng is supposed to deliver
-        // unprocessed data.
-        trng.mem.writel_relaxed(0, EXYNOS_TRNG_POST_CTRL);
+        // Post-processing is bypassed by default so /dev/hwrng gets raw output; the
+        // `samsung,post-processing` DT property opts back in.
+        val = if post_processing {
+            EXYNOS_TRNG_POST_CTRL_EN
+        } else {
+            0
+        };
+        trng.mem.writel_relaxed(val.try_into()?, EXYNOS_TRNG_POST_CTRL);
+
+        // Arm the hardware's own online chi-square health monitor when the
+        // `samsung,online-test` DT property asks for it.
+        val = if online_monitor {
+            EXYNOS_TRNG_ONLINE_CTRL_EN
+        } else {
+            0
+        };
+        trng.mem.writel_relaxed(val.try_into()?, EXYNOS_TRNG_ONLINE_CTRL);
 
         Ok(())
     }
 
-    fn read(trng: RefBorrow<'_, DeviceData>, data: &mut [u8], _wait: bool) -> Result<u32> {
-        let trng = trng.resources().ok_or(Error::ENXIO)?;
+    fn read(trng: RefBorrow<'_, DeviceData>, data: &mut [u8], wait: bool) -> Result<u32> {
+        // The continuous health tests have tripped too many times since this device was probed;
+        // treat it as permanently faulty instead of powering it back up only to fail again.
+        if trng.health.failure_count() >= EXYNOS_TRNG_HEALTH_FAILURE_LIMIT {
+            return Err(Error::ENODEV);
+        }
+
+        // `resume_and_get` may sleep waiting for the parent device, so only take it when `wait`
+        // says we may block; otherwise only claim the device if it is already active, the same
+        // way the FIFO poll below avoids sleeping when `wait == false`. The returned guard keeps
+        // the clock and power domain up across a run of reads and always releases the usage
+        // count on the way out, including on early-return error paths below.
+        let _usage = if wait {
+            pm_runtime::resume_and_get_autosuspend(&trng.dev)?
+        } else {
+            pm_runtime::get_if_in_use_autosuspend(&trng.dev)?.ok_or(Error::EBUSY)?
+        };
+
+        let res = trng.resources().ok_or(Error::ENXIO)?;
         let max: u32 = min(data.len().try_into()?, EXYNOS_TRNG_FIFO_LEN * 4);
 
-        trng.mem.writel_relaxed(max * 8, EXYNOS_TRNG_FIFO_CTRL);
+        res.mem.writel_relaxed(max * 8, EXYNOS_TRNG_FIFO_CTRL);
+
+        // `wait == false` may be called from atomic context, so poll without sleeping then.
+        let _ = if wait {
+            res.mem
+                .readl_poll_timeout(EXYNOS_TRNG_FIFO_CTRL, |val| *val == 0, 200, 1000000)?
+        } else {
+            res.mem.readl_poll_timeout_atomic(
+                EXYNOS_TRNG_FIFO_CTRL,
+                |val| *val == 0,
+                200,
+                1000000,
+            )?
+        };
+
+        res.mem
+            .try_memcpy_fromio(&mut data[..max as _], EXYNOS_TRNG_FIFO_0)?;
 
-        let _ =
-            trng.mem
-                .readl_poll_timeout(EXYNOS_TRNG_FIFO_CTRL, |val| *val == 0, 200, 1000000)?;
+        if trng.online_monitor
+            && res.mem.readl_relaxed(EXYNOS_TRNG_ONLINE_STAT) & EXYNOS_TRNG_ONLINE_STAT_FAIL != 0
+        {
+            let maxchi2 = res.mem.readl_relaxed(EXYNOS_TRNG_ONLINE_MAXCHI2);
+            dev_err!(
+                trng.dev,
+                "online health monitor tripped, maxchi2={}\n",
+                maxchi2
+            );
+            return Err(Error::EIO);
+        }
 
-        trng.mem
-            .try_memcpy_fromio(&mut data[..max as _], EXYNOS_TRNG_FIFO_0)?;
+        trng.health.check(&data[..max as usize])?;
 
         Ok(max)
     }
@@ -128,8 +207,24 @@ impl platform::Driver for ExynosTrngDriver {
         // SAFETY: Dma operations are not used.
         let mem: IoMem<EXYNOS_REG_SIZE> = unsafe { pdev.ioremap_resource(0) }?;
 
-        let disable = pm_runtime::enable(pdev);
-        pm_runtime::resume_and_get(pdev)?;
+        // Both the hardware post-processing block and the online chi-square monitor default to
+        // off, matching the previous hardcoded behaviour, unless the board's DT opts in.
+        let post_processing = pdev.property_read_bool(c_str!("samsung,post-processing"));
+        let online_monitor = pdev.property_read_bool(c_str!("samsung,online-test"));
+
+        let disable = pm_runtime::enable(pdev).clear_autosuspend_on_drop();
+        pm_runtime::set_autosuspend_delay(pdev, EXYNOS_TRNG_AUTOSUSPEND_DELAY_MS);
+        pm_runtime::use_autosuspend(pdev, true);
+
+        // Only held for the duration of registration, which runs `init()` and needs the clock
+        // and power domain up; released again below so the device can actually autosuspend once
+        // probing is done, instead of staying resumed for as long as the driver is bound.
+        let usage = pm_runtime::resume_and_get_autosuspend(pdev)?;
+
+        let health = hwrng::health::HealthTests::new(
+            EXYNOS_TRNG_MIN_ENTROPY_MILLIBITS,
+            EXYNOS_TRNG_APT_CUTOFF,
+        )?;
 
         let mut data = kernel::new_device_data!(
             hwrng::Registration::new(),
@@ -139,6 +234,9 @@ impl platform::Driver for ExynosTrngDriver {
                 disable,
                 // SAFETY: SpinLock is initialized in the same context later.
                 inner: unsafe { SpinLock::new(ExynosTrngDataInner { clk }) },
+                health,
+                post_processing,
+                online_monitor,
             },
             "ExynosTrng::Registrations"
         )?;
@@ -153,11 +251,14 @@ impl platform::Driver for ExynosTrngDriver {
             .as_pinned_mut()
             .register(fmt!("{}", pdev.name()), 0, data.clone())?;
 
+        drop(usage);
+
         Ok(data)
     }
 
     fn remove(trng: &Self::Data) -> Result {
-        pm_runtime::put_sync(&trng.dev);
+        // No persistent usage count to release here: `probe` only holds one across
+        // registration and the `read` path only holds one for the duration of a single read.
         drop(&trng.disable);
         Ok(())
     }
@@ -11,3 +11,11 @@ pub fn usleep_range(min: usize, max: usize) {
     // SAFETY: FFI call.
     unsafe { bindings::usleep_range(min as _, max as _) };
 }
+
+/// Busy-wait for the given number of microseconds.
+///
+/// Unlike [`usleep_range`], this does not sleep and is safe to call from atomic context.
+pub fn udelay(usec: usize) {
+    // SAFETY: FFI call.
+    unsafe { bindings::udelay(usec as _) };
+}
@@ -4,6 +4,9 @@
 //!
 //! C header: [`include/linux/hw_random.h`](../../../../include/linux/hw_random.h)
 
+pub mod health;
+pub mod timeriomem;
+
 use alloc::{boxed::Box, slice::from_raw_parts_mut};
 
 use crate::{
@@ -36,6 +39,31 @@ pub trait HwrngOperations: Sized + 'static {
         buffer: &mut [i8],
         wait: bool,
     ) -> Result<i32>;
+
+    /// Reports whether a word of data is ready to be fetched with [`data_read`], can be left
+    /// undefined.
+    ///
+    /// `wait` indicates whether the callback may block until data becomes available.
+    ///
+    /// [`data_read`]: HwrngOperations::data_read
+    fn data_present(
+        _data: <Self::Data as PointerWrapper>::Borrowed<'_>,
+        _wait: bool,
+    ) -> Result<bool> {
+        Err(Error::EINVAL)
+    }
+
+    /// Reads a single word of data into `out`, can be left undefined.
+    ///
+    /// Only called once [`data_present`] has reported data is available.
+    ///
+    /// [`data_present`]: HwrngOperations::data_present
+    fn data_read(
+        _data: <Self::Data as PointerWrapper>::Borrowed<'_>,
+        _out: &mut [u8],
+    ) -> Result<u32> {
+        Err(Error::EINVAL)
+    }
 }
 
 /// Registration structure for Hardware Random Number Generator driver.
@@ -63,8 +91,16 @@ impl<'a, T: HwrngOperations> Registration<T> {
         } else {
             None
         };
-        hwrng.data_present = None;
-        hwrng.data_read = None;
+        hwrng.data_present = if T::TO_USE.data_present {
+            Some(data_present_callback::<T>)
+        } else {
+            None
+        };
+        hwrng.data_read = if T::TO_USE.data_read {
+            Some(data_read_callback::<T>)
+        } else {
+            None
+        };
         hwrng.read = Some(read_callback::<T>);
 
         hwrng.priv_ = data as _;
@@ -122,6 +158,12 @@ pub struct ToUse {
 
     /// The `cleanup` field of [`struct hwrng`].
     pub cleanup: bool,
+
+    /// The `data_present` field of [`struct hwrng`].
+    pub data_present: bool,
+
+    /// The `data_read` field of [`struct hwrng`].
+    pub data_read: bool,
 }
 
 /// A constant version where all values are to set to `false`, that is, all supported fields will
@@ -129,6 +171,8 @@ pub struct ToUse {
 pub const USE_NONE: ToUse = ToUse {
     init: false,
     cleanup: false,
+    data_present: false,
+    data_read: false,
 };
 
 /// Defines the [`HwrngOperations::TO_USE`] field based on a list of fields to be populated.
@@ -167,6 +211,47 @@ unsafe extern "C" fn cleanup_callback<T: HwrngOperations>(rng: *mut bindings::hw
     T::cleanup(data);
 }
 
+unsafe extern "C" fn data_present_callback<T: HwrngOperations>(
+    rng: *mut bindings::hwrng,
+    wait: c_types::c_int,
+) -> c_types::c_int {
+    from_kernel_result! {
+        // SAFETY: `priv` private data field was initialized during creation of
+        // the `bindings::hwrng` in `Self::init_hwrng` function. This callback
+        // is only called once `new_pinned` suceeded previously which guarantees safety.
+        let data = unsafe { T::Data::borrow((*rng).priv_ as *const core::ffi::c_void) };
+
+        // Unlike `read`, the core treats this return value as a plain C boolean rather than an
+        // errno-checked one, so a driver-side failure must be folded into "not present" here
+        // rather than crossing the FFI boundary as a negative value the core would misread as
+        // truthy.
+        let present = T::data_present(data, wait != 0).unwrap_or(false);
+        Ok(present as c_types::c_int)
+    }
+}
+
+unsafe extern "C" fn data_read_callback<T: HwrngOperations>(
+    rng: *mut bindings::hwrng,
+    data: *mut u32,
+) -> c_types::c_int {
+    from_kernel_result! {
+        // SAFETY: `priv` private data field was initialized during creation of
+        // the `bindings::hwrng` in `Self::init_hwrng` function. This callback
+        // is only called once `new_pinned` suceeded previously which guarantees safety.
+        let drv_data = unsafe { T::Data::borrow((*rng).priv_ as *const core::ffi::c_void) };
+
+        // SAFETY: `data` is a valid pointer to a `u32` as guaranteed by the caller of
+        // `hwrng.data_read`.
+        let out = unsafe { from_raw_parts_mut(data as *mut u8, core::mem::size_of::<u32>()) };
+
+        // As in `data_present_callback`, the core reads this as a plain byte count, not an
+        // errno-checked value, so a failure must be reported as "0 bytes read" here rather than
+        // as a negative value the core would otherwise treat as a successful word.
+        let ret = T::data_read(drv_data, out).unwrap_or(0);
+        Ok(ret as c_types::c_int)
+    }
+}
+
 unsafe extern "C" fn read_callback<T: HwrngOperations>(
     rng: *mut bindings::hwrng,
     data: *mut c_types::c_void,
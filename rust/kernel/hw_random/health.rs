@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Continuous NIST SP 800-90B health tests for raw TRNG output.
+//!
+//! Any [`HwrngOperations::read`] implementation can route freshly produced bytes through
+//! [`HealthTests::check`] before they reach userspace, so a silently failing noise source is
+//! caught instead of handed out as if it were good entropy.
+//!
+//! [`HwrngOperations::read`]: crate::hw_random::HwrngOperations::read
+
+use alloc::boxed::Box;
+use core::pin::Pin;
+
+use crate::{sync::SpinLock, Error, Result};
+
+/// Window size (in samples) of the Adaptive Proportion Test, as recommended by
+/// NIST SP 800-90B for byte-wide samples.
+const APT_WINDOW: u32 = 1024;
+
+/// Continuous SP 800-90B Repetition Count Test and Adaptive Proportion Test, run together over
+/// a stream of byte samples.
+///
+/// `min_entropy_millibits` is the configured per-byte min-entropy estimate `H`, expressed as
+/// milli-bits (i.e. `H * 1000`) so the cutoff can be derived with integer arithmetic. `apt_cutoff`
+/// is the Adaptive Proportion Test cutoff for the chosen `alpha` and [`APT_WINDOW`], taken from
+/// the precomputed tables in SP 800-90B section 4.4.2 since the test has no closed form.
+pub struct HealthTests {
+    min_entropy_millibits: u32,
+    rct_cutoff: u32,
+    apt_cutoff: u32,
+    state: SpinLock<HealthTestState>,
+}
+
+struct HealthTestState {
+    rct_prev: Option<u8>,
+    rct_run: u32,
+    apt_ref: Option<u8>,
+    apt_seen: u32,
+    apt_count: u32,
+    failures: u32,
+}
+
+impl HealthTests {
+    /// Repetition Count Test cutoff `C = 1 + ceil(-log2(alpha) / H)` for `alpha = 2^-20`, derived
+    /// from `min_entropy_millibits` without floating point.
+    fn rct_cutoff(min_entropy_millibits: u32) -> Result<u32> {
+        if min_entropy_millibits == 0 {
+            return Err(Error::EINVAL);
+        }
+
+        // `-log2(2^-20) = 20`, expressed in milli-bits to match `min_entropy_millibits`.
+        const NEG_LOG2_ALPHA_MILLIBITS: u32 = 20 * 1000;
+        let ceil_div =
+            (NEG_LOG2_ALPHA_MILLIBITS + min_entropy_millibits - 1) / min_entropy_millibits;
+
+        Ok(1 + ceil_div)
+    }
+
+    /// Creates a new pair of continuous health tests.
+    ///
+    /// * `min_entropy_millibits` - the configured per-byte min-entropy estimate `H`, in
+    ///   milli-bits.
+    /// * `apt_cutoff` - the Adaptive Proportion Test cutoff for the chosen `alpha`, read from the
+    ///   SP 800-90B tables for [`APT_WINDOW`] samples.
+    pub fn new(min_entropy_millibits: u32, apt_cutoff: u32) -> Result<Box<Self>> {
+        let rct_cutoff = Self::rct_cutoff(min_entropy_millibits)?;
+
+        let mut tests = Box::try_new(Self {
+            min_entropy_millibits,
+            rct_cutoff,
+            apt_cutoff,
+            // SAFETY: `state` is initialized below, before `tests` is used.
+            state: unsafe {
+                SpinLock::new(HealthTestState {
+                    rct_prev: None,
+                    rct_run: 0,
+                    apt_ref: None,
+                    apt_seen: 0,
+                    apt_count: 0,
+                    failures: 0,
+                })
+            },
+        })?;
+
+        // SAFETY: `tests` is heap-allocated and stays at a fixed address for the rest of its
+        // lifetime, so projecting a pin onto one of its fields is sound.
+        let state = unsafe { Pin::new_unchecked(&mut tests.state) };
+        kernel::spinlock_init!(state, "HealthTests::state");
+
+        Ok(tests)
+    }
+
+    /// The configured per-byte min-entropy estimate, in milli-bits.
+    pub fn min_entropy_millibits(&self) -> u32 {
+        self.min_entropy_millibits
+    }
+
+    /// Number of times either test has tripped since construction.
+    ///
+    /// `check`/`check_byte` already turn a single failure into an `Err(Error::EIO)` for the
+    /// current read; this counter lets a caller additionally watch for *repeated* failures
+    /// across reads and decide to disable the device once it crosses some threshold.
+    pub fn failure_count(&self) -> u32 {
+        self.state.lock().failures
+    }
+
+    /// Runs both continuous tests over a single freshly produced byte.
+    ///
+    /// Returns [`Error::EIO`] the moment either test fails; the caller is expected to propagate
+    /// that as a read error rather than release the sample.
+    pub fn check_byte(&self, byte: u8) -> Result {
+        let mut state = self.state.lock();
+
+        let rct_failed = match state.rct_prev {
+            Some(prev) if prev == byte => {
+                state.rct_run += 1;
+                state.rct_run >= self.rct_cutoff
+            }
+            _ => {
+                state.rct_prev = Some(byte);
+                state.rct_run = 1;
+                false
+            }
+        };
+
+        let apt_failed = match state.apt_ref {
+            None => {
+                state.apt_ref = Some(byte);
+                state.apt_seen = 1;
+                state.apt_count = 0;
+                false
+            }
+            Some(reference) => {
+                if byte == reference {
+                    state.apt_count += 1;
+                }
+                state.apt_seen += 1;
+
+                let failed = state.apt_count >= self.apt_cutoff;
+                if state.apt_seen >= APT_WINDOW || failed {
+                    state.apt_ref = None;
+                }
+                failed
+            }
+        };
+
+        if rct_failed || apt_failed {
+            state.failures += 1;
+            return Err(Error::EIO);
+        }
+
+        Ok(())
+    }
+
+    /// Runs both continuous tests over every byte of `data`, in order.
+    ///
+    /// Stops at the first failing byte; bytes after that point are not tested.
+    pub fn check(&self, data: &[u8]) -> Result {
+        for &byte in data {
+            self.check_byte(byte)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Generic, timer-gated MMIO hardware random number generator.
+//!
+//! Mirrors the C `timeriomem-rng` driver: many trivial RNG peripherals expose a single data
+//! register that only refills after a fixed settling period, so the generic core only needs to
+//! know the register offset, its width and the refill period to drive a working `/dev/hwrng`.
+//!
+//! C driver: [`drivers/char/hw_random/timeriomem-rng.c`](../../../../../drivers/char/hw_random/timeriomem-rng.c)
+
+use alloc::boxed::Box;
+use core::pin::Pin;
+
+use crate::{
+    declare_hwrng_operations, delay,
+    hw_random::{HwrngOperations, Registration},
+    io_mem::IoMem,
+    ktime::{self, Ktime},
+    str::CStr,
+    sync::SpinLock,
+    Result,
+};
+
+/// Width of the data register exposed by the peripheral.
+pub enum Width {
+    /// The register holds a single 32-bit word.
+    FourBytes,
+
+    /// The register holds a single 64-bit word.
+    EightBytes,
+}
+
+impl Width {
+    fn len(&self) -> usize {
+        match self {
+            Width::FourBytes => 4,
+            Width::EightBytes => 8,
+        }
+    }
+}
+
+/// Generic timer-gated MMIO hwrng.
+///
+/// Reads the word at `offset` out of `mem`, refusing to read it again until `period_us`
+/// microseconds have elapsed since the previous fetch. A SoC author can register a working
+/// `/dev/hwrng` purely from device-tree-supplied offset/period values, without writing any new
+/// unsafe FFI.
+pub struct TimerIoMemRng<const SIZE: usize> {
+    mem: IoMem<SIZE>,
+    offset: usize,
+    width: Width,
+    period_us: u64,
+    quality: u16,
+    ready_at: SpinLock<Option<Ktime>>,
+}
+
+impl<const SIZE: usize> TimerIoMemRng<SIZE> {
+    /// Creates a new, heap-allocated [`TimerIoMemRng`].
+    pub fn new(
+        mem: IoMem<SIZE>,
+        offset: usize,
+        width: Width,
+        period_us: u64,
+        quality: u16,
+    ) -> Result<Box<Self>> {
+        let mut state = Box::try_new(Self {
+            mem,
+            offset,
+            width,
+            period_us,
+            quality,
+            // SAFETY: `ready_at` is initialized below, before `state` is used.
+            ready_at: unsafe { SpinLock::new(None) },
+        })?;
+
+        // SAFETY: `state` is heap-allocated and stays at a fixed address for the rest of its
+        // lifetime, so projecting a pin onto one of its fields is sound.
+        let ready_at = unsafe { Pin::new_unchecked(&mut state.ready_at) };
+        kernel::spinlock_init!(ready_at, "TimerIoMemRng::ready_at");
+
+        Ok(state)
+    }
+
+    /// Registers this device as a `/dev/hwrng` under `name`.
+    pub fn register(
+        self: Box<Self>,
+        name: &'static CStr,
+    ) -> Result<Pin<Box<Registration<Self>>>> {
+        let quality = self.quality;
+        Registration::new_pinned(name, quality, self)
+    }
+
+    /// Reads the current word out of the mapped register.
+    fn read_word(&self) -> [u8; 8] {
+        let mut out = [0u8; 8];
+        match self.width {
+            Width::FourBytes => {
+                let val = self.mem.readl_relaxed(self.offset);
+                out[..4].copy_from_slice(&val.to_ne_bytes());
+            }
+            Width::EightBytes => {
+                let val = self.mem.readq_relaxed(self.offset);
+                out.copy_from_slice(&val.to_ne_bytes());
+            }
+        }
+        out
+    }
+}
+
+impl<const SIZE: usize> HwrngOperations for TimerIoMemRng<SIZE> {
+    declare_hwrng_operations!();
+
+    type Data = Box<Self>;
+
+    fn read(data: &Self, buffer: &mut [i8], wait: bool) -> Result<i32> {
+        let len = data.width.len();
+
+        loop {
+            let now = ktime::get();
+            let mut ready_at = data.ready_at.lock();
+
+            if ready_at.as_ref().map_or(true, |t| now >= *t) {
+                let word = data.read_word();
+                *ready_at = Some(now.add_us(data.period_us));
+                drop(ready_at);
+
+                for (dst, src) in buffer.iter_mut().zip(word.iter()).take(len) {
+                    *dst = *src as i8;
+                }
+
+                return Ok(len as i32);
+            }
+            drop(ready_at);
+
+            if !wait {
+                return Ok(0);
+            }
+
+            delay::usleep_range((data.period_us as usize >> 2) + 1, data.period_us as usize);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Memory-mapped IO.
+//!
+//! C header: [`include/asm-generic/io.h`](../../../../include/asm-generic/io.h)
+
+use core::mem::size_of;
+
+use crate::{
+    bindings, c_types,
+    error::Result,
+    iopoll::{readx_poll_timeout, readx_poll_timeout_atomic},
+};
+
+/// An MMIO region of `SIZE` bytes.
+///
+/// # Invariants
+///
+/// `ptr` is a non-null pointer to a mapping of at least `SIZE` bytes, valid for as long as the
+/// `IoMem` exists.
+pub struct IoMem<const SIZE: usize> {
+    ptr: *mut c_types::c_void,
+}
+
+impl<const SIZE: usize> IoMem<SIZE> {
+    /// Wraps an already-mapped MMIO region of at least `SIZE` bytes.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a non-null pointer to an MMIO mapping of at least `SIZE` bytes that stays
+    /// valid for as long as the returned `IoMem` exists.
+    pub unsafe fn from_raw(ptr: *mut c_types::c_void) -> Self {
+        Self { ptr }
+    }
+
+    /// Returns the address of `len` bytes at `offset`, after checking they are within `SIZE`.
+    fn addr(&self, offset: usize, len: usize) -> *mut c_types::c_void {
+        assert!(offset + len <= SIZE);
+        // SAFETY: `offset + len` was just checked to fall within the mapping, which is valid for
+        // the lifetime of `self` by the type invariant.
+        unsafe { self.ptr.add(offset) }
+    }
+
+    /// Reads a 32-bit value at `offset`, without memory barriers.
+    pub fn readl_relaxed(&self, offset: usize) -> u32 {
+        let addr = self.addr(offset, size_of::<u32>());
+        // SAFETY: `addr` points `size_of::<u32>()` bytes into a valid MMIO mapping.
+        unsafe { bindings::__raw_readl(addr) }
+    }
+
+    /// Reads a 64-bit value at `offset`, without memory barriers.
+    pub fn readq_relaxed(&self, offset: usize) -> u64 {
+        let addr = self.addr(offset, size_of::<u64>());
+        // SAFETY: `addr` points `size_of::<u64>()` bytes into a valid MMIO mapping.
+        unsafe { bindings::__raw_readq(addr) }
+    }
+
+    /// Writes a 32-bit `value` at `offset`, without memory barriers.
+    pub fn writel_relaxed(&self, value: u32, offset: usize) {
+        let addr = self.addr(offset, size_of::<u32>());
+        // SAFETY: `addr` points `size_of::<u32>()` bytes into a valid MMIO mapping.
+        unsafe { bindings::__raw_writel(value, addr) };
+    }
+
+    /// Copies `dest.len()` bytes out of the mapping starting at `offset`.
+    pub fn try_memcpy_fromio(&self, dest: &mut [u8], offset: usize) -> Result {
+        let addr = self.addr(offset, dest.len());
+        // SAFETY: `addr` points `dest.len()` bytes into a valid MMIO mapping and `dest` is valid
+        // for writes of its own length.
+        unsafe { bindings::memcpy_fromio(dest.as_mut_ptr() as _, addr, dest.len()) };
+        Ok(())
+    }
+
+    /// Polls the 32-bit register at `offset` until `cond` is satisfied or `timeout_us` elapses,
+    /// sleeping between reads.
+    ///
+    /// Must not be called from atomic context if `sleep_us` or `timeout_us` are nonzero; use
+    /// [`readl_poll_timeout_atomic`] there instead.
+    ///
+    /// [`readl_poll_timeout_atomic`]: IoMem::readl_poll_timeout_atomic
+    pub fn readl_poll_timeout<F: Fn(&u32) -> bool>(
+        &self,
+        offset: usize,
+        cond: F,
+        sleep_us: usize,
+        timeout_us: u64,
+    ) -> Result<u32> {
+        let addr = self.addr(offset, size_of::<u32>());
+
+        unsafe extern "C" fn read_op(addr: *const c_types::c_void) -> u32 {
+            // SAFETY: `addr` is a valid MMIO address by the caller's contract.
+            unsafe { bindings::__raw_readl(addr as *mut _) }
+        }
+
+        // SAFETY: `read_op` and `addr` point at a valid, live MMIO mapping.
+        unsafe { readx_poll_timeout(read_op, cond, sleep_us, timeout_us, addr) }
+    }
+
+    /// Polls the 32-bit register at `offset` until `cond` is satisfied or `timeout_us` elapses,
+    /// busy-waiting between reads so it may be called from atomic/non-sleeping context.
+    pub fn readl_poll_timeout_atomic<F: Fn(&u32) -> bool>(
+        &self,
+        offset: usize,
+        cond: F,
+        delay_us: usize,
+        timeout_us: u64,
+    ) -> Result<u32> {
+        let addr = self.addr(offset, size_of::<u32>());
+
+        unsafe extern "C" fn read_op(addr: *const c_types::c_void) -> Result<u32> {
+            // SAFETY: `addr` is a valid MMIO address by the caller's contract.
+            Ok(unsafe { bindings::__raw_readl(addr as *mut _) })
+        }
+
+        // SAFETY: `read_op` and `addr` point at a valid, live MMIO mapping.
+        unsafe { readx_poll_timeout_atomic(read_op, cond, delay_us, timeout_us, addr) }
+    }
+}
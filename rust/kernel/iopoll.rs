@@ -65,7 +65,46 @@ macro_rules! read_poll_timeout {
                 Err(Error::ETIMEDOUT)
             }
         }
-    }
+    };
+
+    // Atomic variant: busy-waits with `delay::udelay` instead of sleeping, so it may be called
+    // from atomic/non-sleeping context.
+    (atomic: $op:ident, $cond:expr, $delay_us:expr, $timeout_us:expr,
+     $delay_before_read:literal, $( $args:expr ),*) => {
+        {
+            let delay_us: usize = $delay_us;
+            let timeout_us: u64 = $timeout_us;
+            let delay_before_read: bool = $delay_before_read;
+            let timeout = ktime::get().add_us(timeout_us);
+
+            if delay_before_read && delay_us != 0 {
+                delay::udelay(delay_us);
+            }
+
+            let val = loop {
+                // SAFETY: `op` is valid by the safety contract.
+                let val = unsafe { $op($( $args ),*) }?;
+                if $cond(&val) {
+                    break val;
+                }
+
+                if timeout_us != 0 && ktime::get() > timeout {
+                    // SAFETY: `op` is valid by the safety contract.
+                    break unsafe { $op($( $args ),*) }?;
+                }
+
+                if delay_us != 0 {
+                    delay::udelay(delay_us);
+                }
+            };
+
+            if $cond(&val) {
+                Ok(val)
+            } else {
+                Err(Error::ETIMEDOUT)
+            }
+        }
+    };
 }
 
 /// Periodically polls an address until a condition is met or a timeout occurs.
@@ -94,3 +133,31 @@ pub unsafe fn readx_poll_timeout<T, F: Fn(&T) -> bool>(
     // SAFETY: `op` and `addr` are valid by the safety contract.
     read_poll_timeout!(op, cond, sleep_us, timeout_us, false, addr)
 }
+
+/// Periodically polls an address until a condition is met or a timeout occurs, busy-waiting with
+/// `delay::udelay` instead of sleeping.
+///
+/// Mirrors the C `read_poll_timeout_atomic` helper: unlike [`readx_poll_timeout`], this may be
+/// called from atomic/non-sleeping context, at the cost of tying up the CPU while polling.
+///
+/// - `op`: poll function, takes `args` as its arguments and returns `Result<T>`; an `Err` aborts
+///    polling immediately instead of retrying until the timeout,
+/// - `cond`: break condition,
+/// - `delay_us`: time to busy-wait between reads in us (0 tight-loops),
+/// - `timeout_us`: timeout in `us`, 0 means never timeout,
+/// - `args`: arguments for `op` poll function.
+///
+/// # Safety
+///
+/// `op` must be non-null function pointer or other callable object.
+/// `addr` must be non-null i/o address.
+pub unsafe fn readx_poll_timeout_atomic<T, F: Fn(&T) -> bool>(
+    op: unsafe extern "C" fn(*const c_types::c_void) -> Result<T>,
+    cond: F,
+    delay_us: usize,
+    timeout_us: u64,
+    addr: *const c_types::c_void,
+) -> Result<T> {
+    // SAFETY: `op` and `addr` are valid by the safety contract.
+    read_poll_timeout!(atomic: op, cond, delay_us, timeout_us, false, addr)
+}
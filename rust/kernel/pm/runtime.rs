@@ -17,22 +17,77 @@ pub fn enable(dev: &impl RawDevice) -> DisableGuard {
 /// Guard that disables runtime PM of device `dev`.
 pub struct DisableGuard {
     dev: device::Device,
+    clear_autosuspend: bool,
 }
 
 impl DisableGuard {
     /// Create new instance of a guard of device `dev`.
     fn new(dev: device::Device) -> Self {
-        Self { dev }
+        Self {
+            dev,
+            clear_autosuspend: false,
+        }
+    }
+
+    /// Also clear the device's autosuspend configuration when this guard is dropped.
+    pub fn clear_autosuspend_on_drop(mut self) -> Self {
+        self.clear_autosuspend = true;
+        self
     }
 }
 
 impl Drop for DisableGuard {
     fn drop(&mut self) {
+        if self.clear_autosuspend {
+            // SAFETY: Satisfied by the safety requirements of `RawDevice`.
+            unsafe { bindings::pm_runtime_dont_use_autosuspend(self.dev.raw_device()) };
+        }
         // SAFETY: Satisfied by the safety requirements of `RawDevice`.
         unsafe { bindings::pm_runtime_disable(self.dev.raw_device()) };
     }
 }
 
+/// Set the autosuspend delay of `dev`, in milliseconds.
+///
+/// A negative value tells the PM core to autosuspend immediately once idle.
+pub fn set_autosuspend_delay(dev: &impl RawDevice, delay_ms: i32) {
+    // SAFETY: Satisfied by the safety requirements of `RawDevice`.
+    unsafe { bindings::pm_runtime_set_autosuspend_delay(dev.raw_device(), delay_ms) };
+}
+
+/// Enable or disable autosuspend handling for `dev`.
+///
+/// While enabled, [`put_autosuspend`] defers the actual suspend by the configured
+/// [`set_autosuspend_delay`] instead of suspending as soon as the usage counter hits 0.
+pub fn use_autosuspend(dev: &impl RawDevice, enable: bool) {
+    if enable {
+        // SAFETY: Satisfied by the safety requirements of `RawDevice`.
+        unsafe { bindings::pm_runtime_use_autosuspend(dev.raw_device()) };
+    } else {
+        // SAFETY: Satisfied by the safety requirements of `RawDevice`.
+        unsafe { bindings::pm_runtime_dont_use_autosuspend(dev.raw_device()) };
+    }
+}
+
+/// Mark `dev` as having just been used.
+///
+/// Postpones the autosuspend of `dev` configured via [`set_autosuspend_delay`] to start counting
+/// down from now.
+pub fn mark_last_busy(dev: &impl RawDevice) {
+    // SAFETY: Satisfied by the safety requirements of `RawDevice`.
+    unsafe { bindings::pm_runtime_mark_last_busy(dev.raw_device()) };
+}
+
+/// Drop runtime PM usage counter of a device, deferring suspend by its autosuspend delay.
+///
+/// Decrements the runtime PM usage counter of `dev` and if it turns out to be equal to 0,
+/// schedules suspend to run after the delay configured via [`set_autosuspend_delay`] instead of
+/// suspending immediately, unless [`use_autosuspend`] was never called for `dev`.
+pub fn put_autosuspend(dev: &impl RawDevice) {
+    // SAFETY: Satisfied by the safety requirements of `RawDevice`.
+    unsafe { bindings::pm_runtime_put_autosuspend(dev.raw_device()) };
+}
+
 /// Drop runtime PM usage counter of a device.
 /// Decrement the runtime PM usage counter of `dev` unless it is 0 already.
 pub fn put_noidle(dev: &impl RawDevice) {
@@ -60,7 +115,59 @@ pub fn put_sync(dev: &impl RawDevice) {
 /// Resume `dev` synchronously and if that is successful, increment its runtime
 /// PM usage counter. Return error if the runtime PM usage counter of `dev`
 /// has not been incremented.
+///
+/// May sleep, so must not be called from atomic context; see [`get_if_in_use`] for a
+/// non-blocking alternative.
 pub fn resume_and_get(dev: &impl RawDevice) -> Result {
     // SAFETY: Satisfied by the safety requirements of `RawDevice`.
     to_result(|| unsafe { bindings::pm_runtime_resume_and_get(dev.raw_device()) })
 }
+
+/// Bumps up the usage counter of `dev` only if it is already active, without resuming it.
+///
+/// Unlike [`resume_and_get`], this never sleeps, so it is safe to call from atomic context.
+/// Returns `true` (and increments the usage counter) if `dev` was already active, or `false`
+/// (leaving the usage counter untouched) if it was not.
+pub fn get_if_in_use(dev: &impl RawDevice) -> Result<bool> {
+    // SAFETY: Satisfied by the safety requirements of `RawDevice`.
+    let ret = unsafe { bindings::pm_runtime_get_if_in_use(dev.raw_device()) };
+    if ret < 0 {
+        return Err(crate::Error::from_kernel_errno(ret));
+    }
+    Ok(ret > 0)
+}
+
+/// Guard that marks a device as last used and releases its runtime PM usage counter, deferring
+/// the actual suspend by its configured autosuspend delay, when dropped.
+///
+/// Obtained from [`resume_and_get_autosuspend`] or [`get_if_in_use_autosuspend`] so the usage
+/// counter acquired on a `/dev/hwrng`-style read is always released, including on early-return
+/// error paths.
+pub struct UsageGuard<'a, T: RawDevice> {
+    dev: &'a T,
+}
+
+impl<T: RawDevice> Drop for UsageGuard<'_, T> {
+    fn drop(&mut self) {
+        mark_last_busy(self.dev);
+        put_autosuspend(self.dev);
+    }
+}
+
+/// Like [`resume_and_get`], but returns a guard that releases the usage counter (deferring
+/// suspend by the configured autosuspend delay) when dropped, instead of requiring a matching
+/// manual [`put_autosuspend`] call on every path out of the caller.
+pub fn resume_and_get_autosuspend<T: RawDevice>(dev: &T) -> Result<UsageGuard<'_, T>> {
+    resume_and_get(dev)?;
+    Ok(UsageGuard { dev })
+}
+
+/// Like [`get_if_in_use`], but returns a releasing [`UsageGuard`] instead of `true` when the
+/// usage counter was incremented.
+pub fn get_if_in_use_autosuspend<T: RawDevice>(dev: &T) -> Result<Option<UsageGuard<'_, T>>> {
+    Ok(if get_if_in_use(dev)? {
+        Some(UsageGuard { dev })
+    } else {
+        None
+    })
+}